@@ -0,0 +1,15 @@
+//! Common alphabets for [`Rng::string`](crate::Rng::string).
+
+/// ASCII digits and letters, `[0-9A-Za-z]`.
+pub const ALPHANUMERIC: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Lowercase hexadecimal digits, `[0-9a-f]`.
+pub const HEX: &str = "0123456789abcdef";
+
+/// The RFC 4648 base32 alphabet, `[A-Z2-7]`.
+pub const BASE32: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The RFC 4648 URL- and filename-safe base64 alphabet, `[A-Za-z0-9_-]`.
+///
+/// This is also the default Nano ID alphabet.
+pub const BASE64URL: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
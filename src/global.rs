@@ -0,0 +1,209 @@
+//! Free functions that draw from the thread-local generator.
+
+use std::ops::RangeBounds;
+
+use crate::{Rng, RNG};
+
+/// Reseeds the thread-local generator, making subsequent calls on this thread
+/// deterministic.
+pub fn seed(seed: u128) {
+    RNG.with(|r| r.0.set(Rng::with_seed(seed).0.get()));
+}
+
+/// Fills `bytes` with random data.
+pub fn fill_bytes(bytes: &mut [u8]) {
+    RNG.with(|r| r.fill_bytes(bytes));
+}
+
+/// Generates a random `bool`.
+pub fn bool() -> bool {
+    RNG.with(|r| r.bool())
+}
+
+/// Returns `true` with probability `p`.
+///
+/// # Panics
+///
+/// Panics if `p` is not within `[0, 1]`.
+pub fn chance(p: f64) -> bool {
+    RNG.with(|r| r.chance(p))
+}
+
+/// Returns `true` with the exact probability `numerator / denominator`.
+///
+/// # Panics
+///
+/// Panics if `denominator` is zero or smaller than `numerator`.
+pub fn ratio(numerator: u64, denominator: u64) -> bool {
+    RNG.with(|r| r.ratio(numerator, denominator))
+}
+
+macro_rules! global_integer {
+    ($($fn:ident, $range_fn:ident, $t:ty;)*) => {
+        $(
+            #[doc = concat!("Generates a random `", stringify!($t), "`.")]
+            pub fn $fn() -> $t {
+                RNG.with(|r| r.$fn())
+            }
+
+            #[doc = concat!("Generates a `", stringify!($t), "` uniformly distributed within the given range.")]
+            ///
+            /// # Panics
+            ///
+            /// Panics if the range is empty.
+            pub fn $range_fn(range: impl RangeBounds<$t>) -> $t {
+                RNG.with(|r| r.$range_fn(range))
+            }
+        )*
+    };
+}
+
+global_integer! {
+    u128, u128_range, u128;
+    u64, u64_range, u64;
+    u32, u32_range, u32;
+    u16, u16_range, u16;
+    u8, u8_range, u8;
+    usize, usize_range, usize;
+    i128, i128_range, i128;
+    i64, i64_range, i64;
+    i32, i32_range, i32;
+    i16, i16_range, i16;
+    i8, i8_range, i8;
+    isize, isize_range, isize;
+}
+
+/// Generates a `char` uniformly distributed within the given range.
+///
+/// # Panics
+///
+/// Panics if the range is empty.
+pub fn char(range: impl RangeBounds<char>) -> char {
+    RNG.with(|r| r.char(range))
+}
+
+/// Generates a `char` uniformly distributed over all Unicode scalar values.
+pub fn any_char() -> char {
+    RNG.with(|r| r.any_char())
+}
+
+/// Generates an ASCII letter or digit, `[0-9A-Za-z]`.
+pub fn alphanumeric() -> char {
+    RNG.with(|r| r.alphanumeric())
+}
+
+/// Generates an ASCII lowercase letter, `[a-z]`.
+pub fn lowercase() -> char {
+    RNG.with(|r| r.lowercase())
+}
+
+/// Generates an ASCII uppercase letter, `[A-Z]`.
+pub fn uppercase() -> char {
+    RNG.with(|r| r.uppercase())
+}
+
+/// Generates a digit in the given radix.
+///
+/// # Panics
+///
+/// Panics if `radix` is not within `[2, 36]`.
+pub fn digit(radix: u32) -> char {
+    RNG.with(|r| r.digit(radix))
+}
+
+/// Generates a `String` of `len` characters drawn uniformly from `charset`.
+///
+/// # Panics
+///
+/// Panics if `charset` is empty.
+pub fn string(charset: &str, len: usize) -> String {
+    RNG.with(|r| r.string(charset, len))
+}
+
+/// Generates a `String` of `len` characters drawn uniformly from `charset`.
+///
+/// # Panics
+///
+/// Panics if `charset` is empty.
+pub fn string_from_chars(charset: &[char], len: usize) -> String {
+    RNG.with(|r| r.string_from_chars(charset, len))
+}
+
+/// Generates a 21 character identifier in the style of Nano ID.
+pub fn nanoid() -> String {
+    RNG.with(|r| r.nanoid())
+}
+
+/// Shuffles `slice` in place with a Fisher–Yates shuffle.
+pub fn shuffle<T>(slice: &mut [T]) {
+    RNG.with(|r| r.shuffle(slice));
+}
+
+/// Moves `k` uniformly chosen elements of `slice` to its front in random
+/// order, and returns that prefix.
+pub fn partial_shuffle<T>(slice: &mut [T], k: usize) -> &mut [T] {
+    RNG.with(|r| r.partial_shuffle(slice, k))
+}
+
+/// Returns a uniformly chosen element of `slice`, or `None` if it is empty.
+pub fn choose<T>(slice: &[T]) -> Option<&T> {
+    RNG.with(|r| r.choose(slice))
+}
+
+/// Returns a mutable reference to a uniformly chosen element of `slice`, or
+/// `None` if it is empty.
+pub fn choose_mut<T>(slice: &mut [T]) -> Option<&mut T> {
+    RNG.with(|r| r.choose_mut(slice))
+}
+
+/// Returns a uniformly chosen item of `iter` using reservoir sampling, or
+/// `None` if it is empty.
+pub fn choose_iter<I: IntoIterator>(iter: I) -> Option<I::Item> {
+    RNG.with(|r| r.choose_iter(iter))
+}
+
+macro_rules! global_float {
+    ($($fn:ident, $t:ty, $interval:literal;)*) => {
+        $(
+            #[doc = concat!("Generates an `", stringify!($t), "` uniformly distributed in `", $interval, "`.")]
+            pub fn $fn() -> $t {
+                RNG.with(|r| r.$fn())
+            }
+        )*
+    };
+}
+
+global_float! {
+    f64, f64, "[0, 1)";
+    f64_open_closed, f64, "(0, 1]";
+    f64_open, f64, "(0, 1)";
+    f64_closed, f64, "[0, 1]";
+    f32, f32, "[0, 1)";
+    f32_open_closed, f32, "(0, 1]";
+    f32_open, f32, "(0, 1)";
+    f32_closed, f32, "[0, 1]";
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn seeded_globals_are_deterministic() {
+        crate::seed(7);
+        let first = (crate::u64(), crate::f64(), crate::i32_range(-3..3));
+
+        crate::seed(7);
+        let second = (crate::u64(), crate::f64(), crate::i32_range(-3..3));
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn globals_match_seeded_rng() {
+        let rng = crate::Rng::with_seed(42);
+        crate::seed(42);
+
+        assert_eq!(crate::u32(), rng.u32());
+        assert_eq!(crate::u8_range(..10), rng.u8_range(..10));
+        assert_eq!(crate::bool(), rng.bool());
+    }
+}
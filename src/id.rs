@@ -0,0 +1,309 @@
+//! Random identifiers: RFC 9562 UUIDs and ULIDs.
+//!
+//! Identifiers are drawn from an [`Rng`], so a seeded generator yields the
+//! same identifiers on every run.
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::Rng;
+
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const ULID_LEN: usize = 26;
+const UUID_LEN: usize = 36;
+const UUID_HYPHENS: [usize; 4] = [8, 13, 18, 23];
+
+/// The error returned when parsing a [`Uuid`] or [`Ulid`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIdError(());
+
+impl fmt::Display for ParseIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid identifier syntax")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseIdError {}
+
+/// A 128-bit UUID, displayed in the canonical hyphenated lowercase form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uuid([u8; 16]);
+
+impl Uuid {
+    /// Creates a UUID from its big-endian bytes.
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Uuid(bytes)
+    }
+
+    /// Returns the big-endian bytes of this UUID.
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// Generates a version 4 UUID, with all 122 non-fixed bits random.
+    pub fn new_v4(rng: &Rng) -> Self {
+        Self::with_version(rng.u128().to_be_bytes(), 4)
+    }
+
+    /// Generates a version 7 UUID from the current Unix time.
+    #[cfg(feature = "std")]
+    pub fn new_v7(rng: &Rng) -> Self {
+        Self::new_v7_at(rng, unix_millis())
+    }
+
+    /// Generates a version 7 UUID from the given Unix time in milliseconds,
+    /// of which the low 48 bits are used, followed by 74 random bits.
+    pub fn new_v7_at(rng: &Rng, unix_ms: u64) -> Self {
+        let mut bytes = rng.u128().to_be_bytes();
+        bytes[..6].copy_from_slice(&unix_ms.to_be_bytes()[2..]);
+
+        Self::with_version(bytes, 7)
+    }
+
+    /// Returns the version number stored in this UUID.
+    pub const fn version(&self) -> u8 {
+        self.0[6] >> 4
+    }
+
+    fn with_version(mut bytes: [u8; 16], version: u8) -> Self {
+        bytes[6] = (bytes[6] & 0x0f) | (version << 4);
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        Uuid(bytes)
+    }
+}
+
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Uuid {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.as_bytes();
+
+        if s.len() != UUID_LEN || UUID_HYPHENS.iter().any(|&i| s[i] != b'-') {
+            return Err(ParseIdError(()));
+        }
+
+        let mut digits = s.iter().filter(|&&c| c != b'-');
+        let mut bytes = [0; 16];
+
+        for byte in &mut bytes {
+            let high = digits.next().and_then(|&c| hex_value(c));
+            let low = digits.next().and_then(|&c| hex_value(c));
+
+            match (high, low) {
+                (Some(high), Some(low)) => *byte = high << 4 | low,
+                _ => return Err(ParseIdError(())),
+            }
+        }
+
+        Ok(Uuid(bytes))
+    }
+}
+
+/// A 128-bit ULID: a 48-bit Unix millisecond timestamp followed by 80 random
+/// bits, displayed as 26 characters of Crockford base32.
+///
+/// ULIDs sort by creation time, to millisecond precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ulid([u8; 16]);
+
+impl Ulid {
+    /// Creates a ULID from its big-endian bytes.
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Ulid(bytes)
+    }
+
+    /// Returns the big-endian bytes of this ULID.
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// Generates a ULID from the current Unix time.
+    #[cfg(feature = "std")]
+    pub fn new(rng: &Rng) -> Self {
+        Self::new_at(rng, unix_millis())
+    }
+
+    /// Generates a ULID from the given Unix time in milliseconds, of which
+    /// the low 48 bits are used.
+    pub fn new_at(rng: &Rng, unix_ms: u64) -> Self {
+        let mut bytes = rng.u128().to_be_bytes();
+        bytes[..6].copy_from_slice(&unix_ms.to_be_bytes()[2..]);
+
+        Ulid(bytes)
+    }
+
+    /// Returns the Unix time in milliseconds stored in this ULID.
+    pub fn timestamp_ms(&self) -> u64 {
+        (u128::from_be_bytes(self.0) >> 80) as u64
+    }
+}
+
+impl fmt::Display for Ulid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = u128::from_be_bytes(self.0);
+
+        for i in (0..ULID_LEN).rev() {
+            let digit = (value >> (5 * i)) as usize & 0x1f;
+            write!(f, "{}", CROCKFORD[digit] as char)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Ulid {
+    type Err = ParseIdError;
+
+    /// Parses a ULID case-insensitively, accepting the Crockford aliases `I`
+    /// and `L` for `1` and `O` for `0`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.as_bytes();
+
+        // 26 base32 digits hold 130 bits, so the first digit may only use 3.
+        if s.len() != ULID_LEN || crockford_value(s[0]).is_none_or(|d| d > 7) {
+            return Err(ParseIdError(()));
+        }
+
+        let value = s.iter().try_fold(0u128, |acc, &c| {
+            crockford_value(c).map(|digit| acc << 5 | u128::from(digit))
+        });
+
+        value.map(|v| Ulid(v.to_be_bytes())).ok_or(ParseIdError(()))
+    }
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
+
+fn crockford_value(c: u8) -> Option<u8> {
+    let c = match c.to_ascii_uppercase() {
+        b'I' | b'L' => b'1',
+        b'O' => b'0',
+        c => c,
+    };
+
+    CROCKFORD.iter().position(|&d| d == c).map(|d| d as u8)
+}
+
+#[cfg(feature = "std")]
+fn unix_millis() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uuid_v4_layout() {
+        let uuid = Uuid::new_v4(&Rng::with_seed(1));
+
+        assert_eq!(uuid.version(), 4);
+        assert_eq!(uuid.as_bytes()[8] & 0xc0, 0x80);
+        assert_eq!(uuid, Uuid::new_v4(&Rng::with_seed(1)));
+    }
+
+    #[test]
+    fn uuid_v7_layout() {
+        let uuid = Uuid::new_v7_at(&Rng::with_seed(1), 0x0123_4567_89ab);
+
+        assert_eq!(uuid.version(), 7);
+        assert_eq!(uuid.as_bytes()[..6], [0x01, 0x23, 0x45, 0x67, 0x89, 0xab]);
+        assert_eq!(uuid.as_bytes()[8] & 0xc0, 0x80);
+        assert!(uuid.to_string().starts_with("01234567-89ab-7"));
+    }
+
+    #[test]
+    fn uuid_round_trip() {
+        let text = "f81d4fae-7dec-11d0-a765-00a0c91e6bf6";
+        let uuid: Uuid = text.parse().unwrap();
+
+        assert_eq!(uuid.to_string(), text);
+        assert_eq!(uuid.version(), 1);
+        assert_eq!("F81D4FAE-7DEC-11D0-A765-00A0C91E6BF6".parse(), Ok(uuid));
+
+        let generated = Uuid::new_v4(&Rng::with_seed(2));
+        assert_eq!(generated.to_string().parse(), Ok(generated));
+    }
+
+    #[test]
+    fn uuid_rejects_malformed() {
+        assert!("f81d4fae7dec11d0a76500a0c91e6bf6".parse::<Uuid>().is_err());
+        assert!("f81d4fae-7dec-11d0-a765-00a0c91e6bg6"
+            .parse::<Uuid>()
+            .is_err());
+        assert!("f81d4fae-7dec-11d0-a765+00a0c91e6bf6"
+            .parse::<Uuid>()
+            .is_err());
+    }
+
+    #[test]
+    fn ulid_round_trip() {
+        let text = "01ARZ3NDEKTSV4RRFFQ69G5FAV";
+        let ulid: Ulid = text.parse().unwrap();
+
+        assert_eq!(ulid.to_string(), text);
+        assert_eq!(ulid.timestamp_ms(), 1469922850259);
+        assert_eq!("01arz3ndektsv4rrffq69g5fav".parse(), Ok(ulid));
+
+        let generated = Ulid::new_at(&Rng::with_seed(2), 1469922850259);
+        assert_eq!(generated.timestamp_ms(), 1469922850259);
+        assert_eq!(generated.to_string().parse(), Ok(generated));
+    }
+
+    #[test]
+    fn ulid_accepts_aliased_leading_digit() {
+        let ulid: Ulid = "01ARZ3NDEKTSV4RRFFQ69G5FAV".parse().unwrap();
+
+        assert_eq!("O1ARZ3NDEKTSV4RRFFQ69G5FAV".parse(), Ok(ulid));
+        assert_eq!("o1arz3ndektsv4rrffq69g5fav".parse(), Ok(ulid));
+        assert_eq!(
+            "I1ARZ3NDEKTSV4RRFFQ69G5FAV".parse(),
+            "11ARZ3NDEKTSV4RRFFQ69G5FAV".parse::<Ulid>()
+        );
+        assert!("l1arz3ndektsv4rrffq69g5fav".parse::<Ulid>().is_ok());
+    }
+
+    #[test]
+    fn ulid_rejects_malformed() {
+        assert!("81ARZ3NDEKTSV4RRFFQ69G5FAV".parse::<Ulid>().is_err());
+        assert!("01ARZ3NDEKTSV4RRFFQ69G5FAU".parse::<Ulid>().is_err());
+        assert!("01ARZ3NDEKTSV4RRFFQ69G5FA".parse::<Ulid>().is_err());
+    }
+
+    #[test]
+    fn ulids_sort_by_time() {
+        let rng = Rng::with_seed(3);
+
+        assert!(Ulid::new_at(&rng, 1000) < Ulid::new_at(&rng, 1001));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn current_time_ids() {
+        let rng = Rng::with_seed(3);
+
+        assert_eq!(Uuid::new_v7(&rng).version(), 7);
+        assert!(Ulid::new(&rng).timestamp_ms() > 1_600_000_000_000);
+    }
+}
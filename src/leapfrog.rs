@@ -0,0 +1,65 @@
+use core::cell::Cell;
+
+/// A generator that produces every `k`-th output of an [`Rng`](crate::Rng)
+/// stream, created with [`Rng::leapfrog`](crate::Rng::leapfrog).
+///
+/// Only the raw `u64` outputs map one-to-one onto the serial stream. Methods
+/// that consume more than one output, such as the bounded ranges when they
+/// reject a sample, draw further values from this worker's own subsequence.
+#[derive(Debug, Clone)]
+pub struct Leapfrog {
+    state: Cell<u128>,
+    stride: u128,
+}
+
+impl Leapfrog {
+    pub(crate) fn new(state: u128, stride: u128) -> Self {
+        Leapfrog {
+            state: Cell::new(state),
+            stride,
+        }
+    }
+
+    #[inline]
+    fn gen_u64(&self) -> u64 {
+        let state = self.state.get().wrapping_mul(self.stride);
+        self.state.set(state);
+
+        (state >> 64) as u64
+    }
+}
+
+impl Leapfrog {
+    rng_methods!();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Rng;
+
+    #[test]
+    fn interleaves_into_serial_stream() {
+        const WORKERS: usize = 3;
+
+        let serial = Rng::with_seed(23);
+        let workers: Vec<_> = (0..WORKERS).map(|i| serial.leapfrog(WORKERS, i)).collect();
+
+        for step in 0..300 {
+            assert_eq!(serial.u64(), workers[step % WORKERS].u64());
+        }
+    }
+
+    #[test]
+    fn single_worker_is_the_serial_stream() {
+        let serial = Rng::with_seed(23);
+        let worker = serial.leapfrog(1, 0);
+
+        assert!((0..100).all(|_| serial.u64() == worker.u64()));
+    }
+
+    #[test]
+    #[should_panic(expected = "less than the stride")]
+    fn index_out_of_range_panics() {
+        Rng::with_seed(0).leapfrog(2, 2);
+    }
+}
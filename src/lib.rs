@@ -1,16 +1,54 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![forbid(unsafe_code)]
 
-use std::cell::Cell;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cell::Cell;
+#[cfg(feature = "std")]
 use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "std")]
 use std::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
 use std::rc::Rc;
+#[cfg(feature = "std")]
 use std::thread;
+#[cfg(feature = "std")]
 use std::time::Instant;
 
+#[macro_use]
+mod methods;
+pub mod charset;
+#[cfg(feature = "std")]
+mod global;
+pub mod id;
+mod leapfrog;
+pub mod net;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+mod sharded;
+#[cfg(target_has_atomic = "64")]
+mod sync;
+
+#[cfg(feature = "std")]
+pub use global::*;
+pub use leapfrog::Leapfrog;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+pub use sharded::ShardedRng;
+#[cfg(target_has_atomic = "64")]
+pub use sync::SyncRng;
+
 const MULT: u128 = 0x12e15e35b500f16e2e714eb2b37916a5;
-const MASK_LOW: u64 = 0x00000000ffffffff;
-const MASK_HIGH: u64 = 0xffffffff00000000;
+const MULT_INV: u128 = inverse(MULT);
+const SPLIT_BITS: u32 = 64;
+const SPLIT_MULT: u128 = pow_mod(MULT, 1 << SPLIT_BITS);
+const EVEN_STATE: &str = "generator state must be odd";
+const MIX_GAMMA: u128 = 0x9e3779b97f4a7c15f39cc0605cedc835;
+const MIX_MULT_1: u128 = 0xbf58476d1ce4e5b994d049bb133111eb;
+const MIX_MULT_2: u128 = 0x94d049bb133111ebbf58476d1ce4e5b9;
 
+#[cfg(feature = "std")]
 thread_local! {
     static RNG: Rc<Rng> = Rc::new(Rng(Cell::new({
         let mut hasher = DefaultHasher::new();
@@ -21,19 +59,229 @@ thread_local! {
     })));
 }
 
+/// A SplitMix-style finalizer widened to 128 bits. Every step is invertible,
+/// so distinct inputs always map to distinct outputs.
+#[inline]
+const fn mix(state: u128) -> u128 {
+    let mut z = state.wrapping_add(MIX_GAMMA);
+    z = (z ^ (z >> 64)).wrapping_mul(MIX_MULT_1);
+    z = (z ^ (z >> 64)).wrapping_mul(MIX_MULT_2);
+    z ^ (z >> 64)
+}
+
+/// Computes the multiplicative inverse of an odd `x` modulo 2^128 by Newton's
+/// iteration, each step doubling the number of correct low bits.
+const fn inverse(x: u128) -> u128 {
+    let mut inv = x;
+    let mut i = 0;
+
+    while i < 7 {
+        inv = inv.wrapping_mul(2u128.wrapping_sub(x.wrapping_mul(inv)));
+        i += 1;
+    }
+
+    inv
+}
+
+/// Computes `base^exp mod 2^128` by square-and-multiply.
+const fn pow_mod(mut base: u128, mut exp: u128) -> u128 {
+    let mut acc = 1u128;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = acc.wrapping_mul(base);
+        }
+        base = base.wrapping_mul(base);
+        exp >>= 1;
+    }
+
+    acc
+}
+
 /// A random number generator.
-#[derive(Debug)]
+///
+/// Cloning copies the current state, so the clone replays exactly the values
+/// this generator would produce next. Use [`Rng::fork`] for an independent
+/// child generator.
+#[derive(Debug, Clone)]
 pub struct Rng(Cell<u128>);
 
 impl Rng {
+    /// Creates a generator forked from the thread-local generator.
+    #[cfg(feature = "std")]
     pub fn new() -> Self {
-        let seed = RNG.with(|r| r.next_state());
-
-        Rng(Cell::new(seed))
+        RNG.with(|r| r.fork())
     }
 
+    /// Creates a generator from a 128-bit seed.
+    ///
+    /// The seed is scrambled before use, so similar seeds start from unrelated
+    /// states and every bit of the seed affects the stream. The generator only
+    /// has 2^127 valid (odd) states, so seeds necessarily pair up, but the
+    /// pairs are scattered pseudo-randomly rather than differing in one bit.
     pub fn with_seed(seed: u128) -> Self {
-        Rng(Cell::new((seed << 1) | 1))
+        Rng(Cell::new(mix(seed) | 1))
+    }
+
+    /// Creates a generator from a 64-bit seed.
+    pub fn with_seed_u64(seed: u64) -> Self {
+        Self::with_seed(seed.into())
+    }
+
+    /// Creates a generator from a seed of arbitrary length.
+    ///
+    /// All bytes and the length of the slice contribute to the seed, so
+    /// slices that differ only in trailing zeroes produce different streams.
+    pub fn from_seed_bytes(bytes: &[u8]) -> Self {
+        let seed = bytes.chunks(16).fold(bytes.len() as u128, |acc, chunk| {
+            let mut block = [0; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+
+            mix(acc ^ u128::from_le_bytes(block))
+        });
+
+        Self::with_seed(seed)
+    }
+
+    /// Creates a generator from a state previously obtained with [`Rng::state`].
+    ///
+    /// Returns `None` if `state` is even, as even states are never reached by
+    /// a valid generator.
+    pub fn from_state(state: u128) -> Option<Self> {
+        (state & 1 == 1).then(|| Rng(Cell::new(state)))
+    }
+
+    /// Returns the current internal state, which can later be passed to
+    /// [`Rng::from_state`] or [`Rng::restore`] to resume from this point.
+    pub fn state(&self) -> u128 {
+        self.0.get()
+    }
+
+    /// Restores a state previously obtained with [`Rng::state`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `state` is even.
+    pub fn restore(&self, state: u128) {
+        assert!(state & 1 == 1, "{}", EVEN_STATE);
+
+        self.0.set(state);
+    }
+
+    /// Advances this generator and returns a child whose state is derived from
+    /// it through a mixing function, so the two streams are uncorrelated.
+    pub fn fork(&self) -> Rng {
+        Rng(Cell::new(mix(self.next_state()) | 1))
+    }
+
+    /// Advances the generator by `n` steps in `O(log n)` time, as if `n`
+    /// values had been drawn with [`Rng::u64`].
+    pub fn advance(&self, n: u128) {
+        self.0.set(self.0.get().wrapping_mul(pow_mod(MULT, n)));
+    }
+
+    /// Returns a new generator positioned `n` steps ahead of this one,
+    /// leaving this generator untouched.
+    pub fn jumped(&self, n: u128) -> Rng {
+        Rng(Cell::new(self.0.get().wrapping_mul(pow_mod(MULT, n))))
+    }
+
+    /// Steps the generator back by `n` steps, undoing `n` draws of
+    /// [`Rng::u64`].
+    pub fn rewind(&self, n: u128) {
+        self.0.set(self.0.get().wrapping_mul(pow_mod(MULT_INV, n)));
+    }
+
+    /// Undoes the most recent step, returning the `u64` it produced.
+    ///
+    /// Calling this after [`Rng::u64`] returns the same value and leaves the
+    /// generator as it was before that draw.
+    pub fn prev_u64(&self) -> u64 {
+        let state = self.0.get();
+        self.0.set(state.wrapping_mul(MULT_INV));
+
+        (state >> 64) as u64
+    }
+
+    /// Splits off a generator that owns the next block of 2^64 steps, then
+    /// jumps this generator past that block.
+    ///
+    /// The period of 2^126 holds 2^62 such blocks, so up to 2^62 splits from
+    /// one generator yield disjoint streams, provided no split generator draws
+    /// more than 2^64 values. The whole set remains reproducible from the
+    /// original seed.
+    pub fn split(&self) -> Rng {
+        let state = self.0.get();
+        self.0.set(state.wrapping_mul(SPLIT_MULT));
+
+        Rng(Cell::new(state))
+    }
+
+    /// Splits off `k` generators over consecutive blocks, as if calling
+    /// [`Rng::split`] `k` times.
+    #[cfg(feature = "alloc")]
+    pub fn split_n(&self, k: usize) -> Vec<Rng> {
+        (0..k).map(|_| self.split()).collect()
+    }
+
+    /// Returns a generator producing outputs `i`, `i + k`, `i + 2k`, … of this
+    /// generator's [`Rng::u64`] stream, leaving this generator untouched.
+    ///
+    /// Handing `leapfrog(k, 0)` through `leapfrog(k, k - 1)` to `k` workers
+    /// partitions the serial stream so that interleaving their `u64` outputs
+    /// reproduces it bit-for-bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= k`.
+    pub fn leapfrog(&self, k: usize, i: usize) -> Leapfrog {
+        assert!(i < k, "leapfrog index must be less than the stride");
+
+        let stride = pow_mod(MULT, k as u128);
+        let state = self
+            .0
+            .get()
+            .wrapping_mul(pow_mod(MULT, i as u128 + 1))
+            .wrapping_mul(inverse(stride));
+
+        Leapfrog::new(state, stride)
+    }
+
+    /// Returns how many steps this generator must advance to reach the state of
+    /// `other`, or `None` if the two states lie on different orbits and can
+    /// never meet.
+    ///
+    /// `MULT` generates the subgroup of residues `1 mod 4`, which has order
+    /// 2^126, so the distance is found bit by bit with Pohlig–Hellman and is
+    /// always less than 2^126.
+    pub fn distance_to(&self, other: &Rng) -> Option<u128> {
+        const ORDER_BITS: u32 = 126;
+
+        let mut target = other.0.get().wrapping_mul(inverse(self.0.get()));
+
+        if target & 3 != 1 {
+            return None;
+        }
+
+        let mut distance = 0;
+        let mut step_inv = MULT_INV;
+
+        for bit in 0..ORDER_BITS {
+            let mut probe = target;
+
+            for _ in 0..ORDER_BITS - 1 - bit {
+                probe = probe.wrapping_mul(probe);
+            }
+
+            if probe != 1 {
+                distance |= 1 << bit;
+                target = target.wrapping_mul(step_inv);
+            }
+
+            step_inv = step_inv.wrapping_mul(step_inv);
+        }
+
+        Some(distance)
     }
 
     #[inline]
@@ -48,77 +296,141 @@ impl Rng {
     fn gen_u64(&self) -> u64 {
         (self.next_state() >> 64) as u64
     }
+}
 
-    pub fn u64(&self) -> u64 {
-        self.gen_u64()
-    }
+impl Rng {
+    rng_methods!();
+}
 
-    pub fn u32(&self) -> u32 {
-        let gen = self.gen_u64();
-        let low = (gen & MASK_LOW) as u32;
-        let high = ((gen & MASK_HIGH) >> 32) as u32;
-        
-        high ^ low
+#[cfg(feature = "std")]
+impl Default for Rng {
+    #[inline]
+    fn default() -> Rng {
+        Rng::new()
     }
+}
 
-    pub fn u16(&self) -> u16 {
-        (self.u32() >> 16) as u16
+/// Reads an endless stream of random bytes.
+#[cfg(feature = "std")]
+impl std::io::Read for &Rng {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.fill_bytes(buf);
+        Ok(buf.len())
     }
+}
 
-    pub fn u8(&self) -> u8 {
-        (self.u32() >> 24) as u8
+/// Reads an endless stream of random bytes.
+#[cfg(feature = "std")]
+impl std::io::Read for Rng {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        (&*self).read(buf)
     }
+}
 
-    pub fn i64(&self) -> i64 {
-        let gen = self.gen_u64().to_le_bytes();
+/// Serializes the state as `[high, low]` 64-bit halves, since many formats
+/// cannot represent a `u128`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rng {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let state = self.state();
 
-        i64::from_le_bytes(gen)
+        [(state >> 64) as u64, state as u64].serialize(serializer)
     }
+}
 
-    pub fn i32(&self) -> i32 {
-        let gen = self.u32().to_le_bytes();
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rng {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [high, low] = <[u64; 2]>::deserialize(deserializer)?;
+        let state = u128::from(high) << 64 | u128::from(low);
 
-        i32::from_le_bytes(gen)
+        Rng::from_state(state).ok_or_else(|| serde::de::Error::custom(EVEN_STATE))
     }
+}
 
-    pub fn i16(&self) -> i16 {
-        let gen = self.u16().to_le_bytes();
+#[cfg(feature = "rand_core")]
+impl rand_core::RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        self.u32()
+    }
 
-        i16::from_le_bytes(gen)
+    fn next_u64(&mut self) -> u64 {
+        self.u64()
     }
 
-    pub fn i8(&self) -> i8 {
-        let gen = self.u8().to_le_bytes();
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Rng::fill_bytes(self, dest)
+    }
 
-        i8::from_le_bytes(gen)
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        rand_core::RngCore::fill_bytes(self, dest);
+        Ok(())
     }
 }
 
-impl Default for Rng {
-    #[inline]
-    fn default() -> Rng {
-        Rng::new()
+#[cfg(feature = "rand_core")]
+impl rand_core::SeedableRng for Rng {
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Rng::with_seed(u128::from_le_bytes(seed))
     }
-}
 
-impl Clone for Rng {
-    fn clone(&self) -> Self {
-        Rng(Cell::new(self.next_state()))
+    fn seed_from_u64(state: u64) -> Self {
+        Rng::with_seed_u64(state)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::methods::{F32_SCALE, F64_SCALE};
     use crate::*;
+    use std::ops::Bound;
 
     #[test]
     fn it_works() {
         let rng = Rng::with_seed(Default::default());
 
         assert_ne!(rng.gen_u64(), 0);
-        assert_eq!(rng.gen_u64(), 4075977849992214257);
+        assert_eq!(rng.gen_u64(), 14328661369045228676);
+    }
+
+    #[test]
+    fn seeds_use_the_full_width() {
+        let seed = 0x1234_5678_9abc_def0;
+
+        assert_ne!(
+            Rng::with_seed(seed).0.get(),
+            Rng::with_seed(seed | 1 << 127).0.get(),
+            "the top bit of the seed must not be discarded"
+        );
+
+        let rng0 = Rng::with_seed(0);
+        let rng1 = Rng::with_seed(1);
+
+        assert!(
+            (rng0.0.get() ^ rng1.0.get()).count_ones() > 32,
+            "adjacent seeds must start from unrelated states"
+        );
     }
 
+    #[test]
+    fn smaller_seeds() {
+        assert_eq!(Rng::with_seed_u64(99).u64(), Rng::with_seed(99).u64());
+
+        let a = Rng::from_seed_bytes(b"lehmerand");
+        let b = Rng::from_seed_bytes(b"lehmerand");
+        let c = Rng::from_seed_bytes(b"lehmerand\0");
+
+        assert_eq!(a.u64(), b.u64());
+        assert_ne!(a.0.get(), c.0.get());
+        assert_ne!(
+            Rng::from_seed_bytes(&[]).0.get(),
+            Rng::from_seed_bytes(&[0]).0.get()
+        );
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn always_unique() {
         let rng1 = Rng::new();
@@ -136,6 +448,93 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn sibling_streams_do_not_overlap() {
+        const N: usize = 1000;
+
+        let rng1 = Rng::new();
+        let rng2 = Rng::new();
+
+        let first: std::collections::HashSet<u64> = (0..N).map(|_| rng1.gen_u64()).collect();
+
+        assert!(
+            (0..N).all(|_| !first.contains(&rng2.gen_u64())),
+            "sibling generators must not share outputs"
+        );
+    }
+
+    #[test]
+    fn advance_matches_stepping() {
+        let stepped = Rng::with_seed(5);
+        let advanced = Rng::with_seed(5);
+
+        for _ in 0..1234 {
+            stepped.gen_u64();
+        }
+        advanced.advance(1234);
+
+        assert_eq!(stepped.0.get(), advanced.0.get());
+
+        let jumped = stepped.jumped(1_000_000_000_000);
+        stepped.advance(999_999_999_999);
+        stepped.gen_u64();
+
+        assert_eq!(jumped.gen_u64(), stepped.gen_u64());
+    }
+
+    #[test]
+    fn rewind_undoes_draws() {
+        assert_eq!(MULT.wrapping_mul(MULT_INV), 1);
+
+        let rng = Rng::with_seed(11);
+        let start = rng.0.get();
+        let drawn: Vec<u64> = (0..10).map(|_| rng.u64()).collect();
+
+        for &value in drawn.iter().rev() {
+            assert_eq!(rng.prev_u64(), value);
+        }
+        assert_eq!(rng.0.get(), start);
+
+        rng.advance(1 << 100);
+        rng.rewind(1 << 100);
+        assert_eq!(rng.0.get(), start);
+    }
+
+    #[test]
+    fn distance_between_states() {
+        let rng = Rng::with_seed(3);
+
+        assert_eq!(rng.distance_to(&rng), Some(0));
+        assert_eq!(rng.distance_to(&rng.jumped(12345)), Some(12345));
+        assert_eq!(
+            rng.distance_to(&rng.jumped(u128::MAX >> 2)),
+            Some(u128::MAX >> 2)
+        );
+
+        let ahead = rng.jumped(7);
+        assert_eq!(ahead.distance_to(&rng), Some((1 << 126) - 7));
+
+        let other_orbit = Rng(Cell::new(rng.0.get().wrapping_mul(3)));
+        assert_eq!(rng.distance_to(&other_orbit), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn splits_are_disjoint_blocks() {
+        let rng = Rng::with_seed(17);
+        let base = Rng(Cell::new(rng.0.get()));
+        let parts = rng.split_n(4);
+
+        for (i, part) in parts.iter().enumerate() {
+            assert_eq!(base.distance_to(part), Some((i as u128) << 64));
+        }
+        assert_eq!(base.distance_to(&rng), Some(4 << 64));
+
+        let again = Rng::with_seed(17).split_n(4);
+        assert!(parts.iter().zip(&again).all(|(a, b)| a.u64() == b.u64()));
+    }
+
     #[test]
     fn deterministic_clone() {
         let rng1 = Rng::with_seed(Default::default());
@@ -151,4 +550,484 @@ mod tests {
         assert_eq!(cloned2.gen_u64(), rng2.gen_u64());
         assert_eq!(cloned1.gen_u64(), cloned2.gen_u64());
     }
+
+    #[test]
+    fn clone_is_a_snapshot() {
+        let rng = Rng::with_seed(9);
+        let state = rng.0.get();
+        let snapshot = rng.clone();
+
+        assert_eq!(rng.0.get(), state, "cloning must not advance the parent");
+        assert!((0..100).all(|_| rng.u64() == snapshot.u64()));
+    }
+
+    #[test]
+    fn fork_decorrelates() {
+        const N: usize = 1000;
+
+        let parent = Rng::with_seed(9);
+        let child = parent.fork();
+
+        let parent_outputs: std::collections::HashSet<u64> =
+            (0..N).map(|_| parent.gen_u64()).collect();
+
+        assert!((0..N).all(|_| !parent_outputs.contains(&child.gen_u64())));
+        assert_eq!(
+            Rng::with_seed(9).fork().u64(),
+            Rng::with_seed(9).fork().u64(),
+            "forking is deterministic"
+        );
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let rng = Rng::with_seed(31);
+        let state = rng.state();
+        let first = rng.u64();
+
+        assert_eq!(Rng::from_state(state).unwrap().u64(), first);
+        assert!(Rng::from_state(state - 1).is_none());
+
+        rng.restore(state);
+        assert_eq!(rng.u64(), first);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be odd")]
+    fn restore_rejects_even_state() {
+        Rng::with_seed(0).restore(2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let rng = Rng::with_seed(31);
+        rng.u64();
+
+        let json = serde_json::to_string(&rng).unwrap();
+        let restored: Rng = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.state(), rng.state());
+        assert!(serde_json::from_str::<Rng>("[0, 2]").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_value_round_trip() {
+        let rng = Rng::with_seed(1);
+
+        let value = serde_json::to_value(&rng).unwrap();
+        let restored: Rng = serde_json::from_value(value).unwrap();
+
+        assert_eq!(restored.state(), rng.state());
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn rand_core_matches_inherent_methods() {
+        use rand_core::{RngCore, SeedableRng};
+
+        let mut rng = Rng::from_seed(5u128.to_le_bytes());
+        let reference = Rng::with_seed(5);
+
+        assert_eq!(rng.next_u64(), reference.u64());
+        assert_eq!(rng.next_u32(), reference.u32());
+        assert_eq!(Rng::seed_from_u64(5).u64(), Rng::with_seed_u64(5).u64());
+
+        let mut bytes = [0; 13];
+        rng.try_fill_bytes(&mut bytes).unwrap();
+        assert_ne!(bytes, [0; 13]);
+    }
+
+    #[test]
+    fn fill_bytes_uses_whole_outputs() {
+        let rng = Rng::with_seed(13);
+        let reference = Rng::with_seed(13);
+
+        let mut bytes = [0; 20];
+        rng.fill_bytes(&mut bytes);
+
+        assert_eq!(bytes[..8], reference.u64().to_le_bytes());
+        assert_eq!(bytes[8..16], reference.u64().to_le_bytes());
+        assert_eq!(bytes[16..], reference.u64().to_le_bytes()[..4]);
+        assert_eq!(rng.u64(), reference.u64());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn reads_as_byte_stream() {
+        use std::io::{self, Read};
+
+        let rng = Rng::with_seed(13);
+        let mut copied = Vec::new();
+
+        io::copy(&mut (&rng).take(100), &mut copied).unwrap();
+        assert_eq!(copied.len(), 100);
+
+        let mut expected = [0; 100];
+        Rng::with_seed(13).fill_bytes(&mut expected);
+        assert_eq!(copied[..], expected[..]);
+    }
+
+    #[test]
+    fn ranges_stay_in_bounds() {
+        let rng = Rng::with_seed(Default::default());
+
+        for _ in 0..1000 {
+            assert!((10..20).contains(&rng.u64_range(10..20)));
+            assert!((-5..=5).contains(&rng.i32_range(-5..=5)));
+            assert!(rng.u8_range(250..) >= 250);
+            assert!(rng.i8_range(..-100) < -100);
+            assert!((1..=3).contains(&rng.usize_range(1..=3)));
+        }
+
+        assert_eq!(rng.u16_range(7..=7), 7);
+        assert_eq!(rng.i64_range(i64::MAX..), i64::MAX);
+    }
+
+    #[test]
+    fn wide_integers() {
+        let rng = Rng::with_seed(21);
+        let reference = Rng::with_seed(21);
+
+        let high = u128::from(reference.u64());
+        let low = u128::from(reference.u64());
+        assert_eq!(rng.u128(), high << 64 | low);
+        assert_eq!(rng.usize(), reference.u64() as usize);
+
+        for _ in 0..1000 {
+            let x = rng.u128_range(1 << 100..=1 << 101);
+            assert!((1 << 100..=1 << 101).contains(&x));
+
+            let x = rng.i128_range(-5..5);
+            assert!((-5..5).contains(&x));
+
+            let x = rng.u128_range(..u128::MAX / 3);
+            assert!(x < u128::MAX / 3);
+        }
+
+        rng.u128_range(..);
+        rng.i128_range(..);
+        assert_eq!(rng.i128_range(i128::MIN..=i128::MIN), i128::MIN);
+    }
+
+    #[test]
+    fn narrow_wide_ranges_match_u64_ranges() {
+        let rng = Rng::with_seed(21);
+        let reference = Rng::with_seed(21);
+
+        for _ in 0..100 {
+            assert_eq!(
+                rng.u128_range(10..1000),
+                u128::from(reference.u64_range(10..1000))
+            );
+        }
+    }
+
+    #[test]
+    fn ranges_cover_full_width() {
+        let rng = Rng::with_seed(Default::default());
+
+        let mut seen = [false; 256];
+
+        for _ in 0..10_000 {
+            seen[rng.u8_range(..) as usize] = true;
+        }
+
+        assert!(seen.iter().all(|&s| s), "every u8 must be reachable");
+
+        rng.u64_range(..);
+        rng.i64_range(i64::MIN..=i64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty range")]
+    fn empty_range_panics() {
+        let rng = Rng::with_seed(Default::default());
+
+        #[allow(clippy::reversed_empty_ranges)]
+        rng.u32_range(5..5);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty range")]
+    fn excluded_max_panics() {
+        let rng = Rng::with_seed(Default::default());
+
+        rng.u8_range((Bound::Excluded(u8::MAX), Bound::Unbounded));
+    }
+
+    #[test]
+    fn coin_flips() {
+        let rng = Rng::with_seed(2);
+
+        assert!((0..1000).all(|_| rng.chance(1.0)));
+        assert!((0..1000).all(|_| !rng.chance(0.0)));
+        assert!((0..1000).all(|_| rng.ratio(7, 7)));
+        assert!((0..1000).all(|_| !rng.ratio(0, 7)));
+
+        let heads = (0..10_000).filter(|_| rng.bool()).count();
+        assert!((4500..5500).contains(&heads));
+
+        let hits = (0..10_000).filter(|_| rng.ratio(1, 4)).count();
+        assert!((2000..3000).contains(&hits));
+
+        let hits = (0..10_000).filter(|_| rng.chance(0.75)).count();
+        assert!((7000..8000).contains(&hits));
+    }
+
+    #[test]
+    #[should_panic(expected = "probability")]
+    fn chance_rejects_invalid_probability() {
+        Rng::with_seed(2).chance(f64::NAN);
+    }
+
+    #[test]
+    #[should_panic(expected = "denominator")]
+    fn ratio_rejects_zero_denominator() {
+        Rng::with_seed(2).ratio(0, 0);
+    }
+
+    #[test]
+    fn chars_skip_surrogates() {
+        let rng = Rng::with_seed(19);
+
+        for _ in 0..1000 {
+            let c = rng.char('\u{d7ff}'..='\u{e000}');
+            assert!(c == '\u{d7ff}' || c == '\u{e000}');
+
+            let c = rng.char((Bound::Excluded('\u{d7ff}'), Bound::Excluded('\u{e001}')));
+            assert_eq!(c, '\u{e000}');
+
+            let c = rng.char('a'..'d');
+            assert!(('a'..'d').contains(&c));
+
+            rng.any_char();
+        }
+    }
+
+    #[test]
+    fn ascii_chars() {
+        let rng = Rng::with_seed(19);
+
+        for _ in 0..1000 {
+            assert!(rng.alphanumeric().is_ascii_alphanumeric());
+            assert!(rng.lowercase().is_ascii_lowercase());
+            assert!(rng.uppercase().is_ascii_uppercase());
+            assert!(rng.digit(16).is_ascii_hexdigit());
+            assert!(matches!(rng.digit(2), '0' | '1'));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "empty range")]
+    fn empty_char_range_panics() {
+        Rng::with_seed(19).char('\u{d7ff}'..'\u{d7ff}');
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn strings_from_charsets() {
+        let rng = Rng::with_seed(29);
+
+        let hex = rng.string(charset::HEX, 32);
+        assert_eq!(hex.len(), 32);
+        assert!(hex.chars().all(|c| charset::HEX.contains(c)));
+
+        let base32 = rng.string(charset::BASE32, 16);
+        assert!(base32.chars().all(|c| charset::BASE32.contains(c)));
+
+        let greek = rng.string("αβγ", 10);
+        assert_eq!(greek.chars().count(), 10);
+        assert!(greek.chars().all(|c| "αβγ".contains(c)));
+
+        let custom = rng.string_from_chars(&['x', 'y'], 8);
+        assert!(custom.chars().all(|c| c == 'x' || c == 'y'));
+
+        assert!(rng.string(charset::ALPHANUMERIC, 0).is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn nanoids() {
+        let rng = Rng::with_seed(29);
+        let id = rng.nanoid();
+
+        assert_eq!(id.len(), 21);
+        assert!(id.chars().all(|c| charset::BASE64URL.contains(c)));
+        assert_ne!(id, rng.nanoid());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic(expected = "empty charset")]
+    fn empty_charset_panics() {
+        Rng::with_seed(29).string("", 4);
+    }
+
+    #[test]
+    fn shuffle_is_a_permutation() {
+        let rng = Rng::with_seed(37);
+        let mut values: Vec<u32> = (0..50).collect();
+
+        rng.shuffle(&mut values);
+        assert_ne!(values, (0..50).collect::<Vec<_>>());
+
+        values.sort_unstable();
+        assert_eq!(values, (0..50).collect::<Vec<_>>());
+
+        let mut again: Vec<u32> = (0..50).collect();
+        let mut reference: Vec<u32> = (0..50).collect();
+        Rng::with_seed(37).shuffle(&mut again);
+        Rng::with_seed(37).shuffle(&mut reference);
+        assert_eq!(again, reference);
+
+        rng.shuffle::<u32>(&mut []);
+    }
+
+    #[test]
+    fn shuffle_is_uniform() {
+        let rng = Rng::with_seed(37);
+        let mut counts = [0; 6];
+
+        for _ in 0..6000 {
+            let mut values = [0, 1, 2];
+            rng.shuffle(&mut values);
+
+            let index = match values {
+                [0, 1, 2] => 0,
+                [0, 2, 1] => 1,
+                [1, 0, 2] => 2,
+                [1, 2, 0] => 3,
+                [2, 0, 1] => 4,
+                _ => 5,
+            };
+            counts[index] += 1;
+        }
+
+        assert!(counts.iter().all(|&c| (800..1200).contains(&c)));
+    }
+
+    #[test]
+    fn partial_shuffle_selects_prefix() {
+        let rng = Rng::with_seed(37);
+        let mut values: Vec<u32> = (0..20).collect();
+
+        let chosen = rng.partial_shuffle(&mut values, 5).to_vec();
+        assert_eq!(chosen.len(), 5);
+        assert_eq!(values[..5], chosen[..]);
+
+        values.sort_unstable();
+        assert_eq!(values, (0..20).collect::<Vec<_>>());
+
+        assert_eq!(rng.partial_shuffle(&mut [1, 2, 3], 10).len(), 3);
+        assert!(rng.partial_shuffle(&mut [1, 2, 3], 0).is_empty());
+    }
+
+    #[test]
+    fn choose_from_slices() {
+        let rng = Rng::with_seed(41);
+        let mut values = [10, 20, 30];
+
+        assert!(rng.choose::<u8>(&[]).is_none());
+        assert!(rng.choose_mut::<u8>(&mut []).is_none());
+
+        for _ in 0..100 {
+            assert!(values.contains(rng.choose(&values).unwrap()));
+        }
+
+        *rng.choose_mut(&mut values).unwrap() = 0;
+        assert_eq!(values.iter().filter(|&&v| v == 0).count(), 1);
+    }
+
+    #[test]
+    fn reservoir_sampling_is_uniform() {
+        let rng = Rng::with_seed(41);
+        let mut counts = [0; 5];
+
+        for _ in 0..5000 {
+            counts[rng.choose_iter(0..5).unwrap()] += 1;
+        }
+
+        assert!(counts.iter().all(|&c| (850..1150).contains(&c)));
+        assert_eq!(rng.choose_iter(Some(7)), Some(7));
+        assert_eq!(rng.choose_iter(core::iter::empty::<u8>()), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn choose_from_collections() {
+        use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+
+        let rng = Rng::with_seed(41);
+
+        let map: BTreeMap<_, _> = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+        let (key, value) = rng.choose_btree_map(&map).unwrap();
+        assert_eq!(map[key], *value);
+
+        let set: BTreeSet<_> = (0..10).collect();
+        assert!(set.contains(rng.choose_btree_set(&set).unwrap()));
+
+        let deque: VecDeque<_> = (0..10).collect();
+        assert!(deque.contains(rng.choose_vec_deque(&deque).unwrap()));
+
+        assert!(rng.choose_btree_map(&BTreeMap::<u8, u8>::new()).is_none());
+        assert!(rng.choose_btree_set(&BTreeSet::<u8>::new()).is_none());
+        assert!(rng.choose_vec_deque(&VecDeque::<u8>::new()).is_none());
+    }
+
+    #[test]
+    fn floats_stay_in_intervals() {
+        let rng = Rng::with_seed(Default::default());
+
+        for _ in 0..1000 {
+            let x = rng.f64();
+            assert!((0.0..1.0).contains(&x));
+            let x = rng.f64_open_closed();
+            assert!(x > 0.0 && x <= 1.0);
+            let x = rng.f64_open();
+            assert!(x > 0.0 && x < 1.0);
+            let x = rng.f64_closed();
+            assert!((0.0..=1.0).contains(&x));
+
+            let x = rng.f32();
+            assert!((0.0..1.0).contains(&x));
+            let x = rng.f32_open_closed();
+            assert!(x > 0.0 && x <= 1.0);
+            let x = rng.f32_open();
+            assert!(x > 0.0 && x < 1.0);
+            let x = rng.f32_closed();
+            assert!((0.0..=1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn float_extremes() {
+        // Odd states whose next output is all zeroes or all ones.
+        let lowest = || Rng(Cell::new(1u128.wrapping_mul(MULT_INV)));
+        let highest = || {
+            Rng(Cell::new(
+                (u128::from(u64::MAX) << 64 | 1).wrapping_mul(MULT_INV),
+            ))
+        };
+
+        assert_eq!(lowest().u64(), 0);
+        assert_eq!(highest().u64(), u64::MAX);
+
+        assert_eq!(lowest().f64(), 0.0);
+        assert_eq!(lowest().f64_open_closed(), F64_SCALE);
+        assert!(lowest().f64_open() > 0.0);
+        assert_eq!(lowest().f32(), 0.0);
+        assert_eq!(lowest().f32_open_closed(), F32_SCALE);
+        assert!(lowest().f32_open() > 0.0);
+
+        assert!(highest().f64() < 1.0);
+        assert_eq!(highest().f64_open_closed(), 1.0);
+        assert!(highest().f64_open() < 1.0);
+        assert_eq!(highest().f64_closed(), 1.0);
+        assert!(highest().f32() < 1.0);
+        assert_eq!(highest().f32_open_closed(), 1.0);
+        assert!(highest().f32_open() < 1.0);
+        assert_eq!(highest().f32_closed(), 1.0);
+    }
 }
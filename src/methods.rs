@@ -0,0 +1,588 @@
+//! The generation methods shared by every generator type.
+//!
+//! Each generator provides a private `gen_u64(&self) -> u64` and then expands
+//! [`rng_methods!`] inside an inherent `impl` block, so all generators expose
+//! the same API on top of their own state handling.
+
+pub(crate) const MASK_LOW: u64 = 0x00000000ffffffff;
+pub(crate) const MASK_HIGH: u64 = 0xffffffff00000000;
+
+pub(crate) const EMPTY_RANGE: &str = "cannot sample from an empty range";
+
+pub(crate) const SURROGATE_START: u32 = 0xd800;
+pub(crate) const SURROGATE_END: u32 = 0xdfff;
+#[cfg(feature = "alloc")]
+pub(crate) const EMPTY_CHARSET: &str = "cannot sample from an empty charset";
+#[cfg(feature = "alloc")]
+pub(crate) const NANOID_LEN: usize = 21;
+
+pub(crate) const F64_MANTISSA: u32 = f64::MANTISSA_DIGITS;
+pub(crate) const F32_MANTISSA: u32 = f32::MANTISSA_DIGITS;
+pub(crate) const F64_SCALE: f64 = 1.0 / (1u64 << F64_MANTISSA) as f64;
+pub(crate) const F32_SCALE: f32 = 1.0 / (1u32 << F32_MANTISSA) as f32;
+
+macro_rules! range_bounds {
+    ($range:expr, $t:ty) => {{
+        use ::core::ops::Bound;
+        use $crate::methods::EMPTY_RANGE;
+
+        let low = match $range.start_bound() {
+            Bound::Included(&x) => x,
+            Bound::Excluded(&x) => x.checked_add(1).expect(EMPTY_RANGE),
+            Bound::Unbounded => <$t>::MIN,
+        };
+        let high = match $range.end_bound() {
+            Bound::Included(&x) => x,
+            Bound::Excluded(&x) => x.checked_sub(1).expect(EMPTY_RANGE),
+            Bound::Unbounded => <$t>::MAX,
+        };
+        assert!(low <= high, "{}", EMPTY_RANGE);
+
+        (low, high)
+    }};
+}
+
+macro_rules! range_integer {
+    ($(#[$attr:meta])* $fn:ident, $t:ty, $unsigned:ty) => {
+        $(#[$attr])*
+        ///
+        /// # Panics
+        ///
+        /// Panics if the range is empty.
+        pub fn $fn(&self, range: impl ::core::ops::RangeBounds<$t>) -> $t {
+            let (low, high) = range_bounds!(range, $t);
+            let span = high.wrapping_sub(low) as $unsigned as u64;
+
+            if span == u64::MAX {
+                return self.gen_u64() as $t;
+            }
+
+            low.wrapping_add(self.gen_bounded_u64(span + 1) as $t)
+        }
+    };
+}
+
+macro_rules! range_wide_integer {
+    ($(#[$attr:meta])* $fn:ident, $t:ty) => {
+        $(#[$attr])*
+        ///
+        /// Spans that fit in 64 bits use the same sampling as the narrower
+        /// range methods; wider spans are sampled by masked rejection.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the range is empty.
+        pub fn $fn(&self, range: impl ::core::ops::RangeBounds<$t>) -> $t {
+            let (low, high) = range_bounds!(range, $t);
+            let span = high.wrapping_sub(low) as u128;
+
+            let offset = if span < u128::from(u64::MAX) {
+                u128::from(self.gen_bounded_u64(span as u64 + 1))
+            } else if span == u128::from(u64::MAX) {
+                u128::from(self.gen_u64())
+            } else {
+                let mask = u128::MAX >> span.leading_zeros();
+
+                loop {
+                    let candidate = self.u128() & mask;
+
+                    if candidate <= span {
+                        break candidate;
+                    }
+                }
+            };
+
+            low.wrapping_add(offset as $t)
+        }
+    };
+}
+
+macro_rules! rng_methods {
+    () => {
+        /// Generates a value in `[0, n)` using Lemire's widening multiply with
+        /// rejection, so the result is free of modulo bias. `n` must be non-zero.
+        #[inline]
+        fn gen_bounded_u64(&self, n: u64) -> u64 {
+            let mut m = u128::from(self.gen_u64()) * u128::from(n);
+
+            if (m as u64) < n {
+                let threshold = n.wrapping_neg() % n;
+
+                while (m as u64) < threshold {
+                    m = u128::from(self.gen_u64()) * u128::from(n);
+                }
+            }
+
+            (m >> 64) as u64
+        }
+
+        /// Fills `bytes` with random data, using every byte of each 64-bit
+        /// output.
+        pub fn fill_bytes(&self, bytes: &mut [u8]) {
+            let mut chunks = bytes.chunks_exact_mut(8);
+
+            for chunk in &mut chunks {
+                chunk.copy_from_slice(&self.gen_u64().to_le_bytes());
+            }
+
+            let remainder = chunks.into_remainder();
+
+            if !remainder.is_empty() {
+                let len = remainder.len();
+                remainder.copy_from_slice(&self.gen_u64().to_le_bytes()[..len]);
+            }
+        }
+
+        /// Generates a random `bool`.
+        pub fn bool(&self) -> bool {
+            self.gen_u64() >> 63 == 1
+        }
+
+        /// Returns `true` with probability `p`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `p` is not within `[0, 1]`.
+        pub fn chance(&self, p: f64) -> bool {
+            assert!(
+                (0.0..=1.0).contains(&p),
+                "probability must be within [0, 1]"
+            );
+
+            self.f64() < p
+        }
+
+        /// Returns `true` with the exact probability
+        /// `numerator / denominator`, without going through floating point.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `denominator` is zero or smaller than `numerator`.
+        pub fn ratio(&self, numerator: u64, denominator: u64) -> bool {
+            assert!(
+                denominator != 0 && numerator <= denominator,
+                "ratio must satisfy 0 <= numerator <= denominator and denominator > 0"
+            );
+
+            self.gen_bounded_u64(denominator) < numerator
+        }
+
+        /// Generates a random `u128` from two consecutive 64-bit outputs.
+        pub fn u128(&self) -> u128 {
+            (u128::from(self.gen_u64()) << 64) | u128::from(self.gen_u64())
+        }
+
+        pub fn u64(&self) -> u64 {
+            self.gen_u64()
+        }
+
+        pub fn u32(&self) -> u32 {
+            use $crate::methods::{MASK_HIGH, MASK_LOW};
+
+            let gen = self.gen_u64();
+            let low = (gen & MASK_LOW) as u32;
+            let high = ((gen & MASK_HIGH) >> 32) as u32;
+
+            high ^ low
+        }
+
+        pub fn u16(&self) -> u16 {
+            (self.u32() >> 16) as u16
+        }
+
+        pub fn u8(&self) -> u8 {
+            (self.u32() >> 24) as u8
+        }
+
+        /// Generates a random `usize`.
+        ///
+        /// This always consumes one 64-bit output. On 64-bit targets the whole
+        /// output is returned; on 32-bit targets only its low 32 bits are, so
+        /// use [`usize_range`](Self::usize_range) for values that must agree
+        /// across platforms.
+        pub fn usize(&self) -> usize {
+            self.gen_u64() as usize
+        }
+
+        /// Generates a random `i128` from two consecutive 64-bit outputs.
+        pub fn i128(&self) -> i128 {
+            let gen = self.u128().to_le_bytes();
+
+            i128::from_le_bytes(gen)
+        }
+
+        pub fn i64(&self) -> i64 {
+            let gen = self.gen_u64().to_le_bytes();
+
+            i64::from_le_bytes(gen)
+        }
+
+        pub fn i32(&self) -> i32 {
+            let gen = self.u32().to_le_bytes();
+
+            i32::from_le_bytes(gen)
+        }
+
+        pub fn i16(&self) -> i16 {
+            let gen = self.u16().to_le_bytes();
+
+            i16::from_le_bytes(gen)
+        }
+
+        pub fn i8(&self) -> i8 {
+            let gen = self.u8().to_le_bytes();
+
+            i8::from_le_bytes(gen)
+        }
+
+        /// Generates a random `isize`.
+        ///
+        /// This always consumes one 64-bit output. On 64-bit targets the whole
+        /// output is returned; on 32-bit targets only its low 32 bits are, so
+        /// use [`isize_range`](Self::isize_range) for values that must agree
+        /// across platforms.
+        pub fn isize(&self) -> isize {
+            self.gen_u64() as isize
+        }
+
+        range_wide_integer!(
+            /// Generates a `u128` uniformly distributed within the given range.
+            u128_range, u128
+        );
+        range_wide_integer!(
+            /// Generates an `i128` uniformly distributed within the given range.
+            i128_range, i128
+        );
+        range_integer!(
+            /// Generates a `u64` uniformly distributed within the given range.
+            u64_range, u64, u64
+        );
+        range_integer!(
+            /// Generates a `u32` uniformly distributed within the given range.
+            u32_range, u32, u32
+        );
+        range_integer!(
+            /// Generates a `u16` uniformly distributed within the given range.
+            u16_range, u16, u16
+        );
+        range_integer!(
+            /// Generates a `u8` uniformly distributed within the given range.
+            u8_range, u8, u8
+        );
+        range_integer!(
+            /// Generates a `usize` uniformly distributed within the given range.
+            ///
+            /// The value is always derived from 64-bit outputs, so a given range
+            /// yields the same results on 32-bit and 64-bit targets.
+            usize_range, usize, usize
+        );
+        range_integer!(
+            /// Generates an `i64` uniformly distributed within the given range.
+            i64_range, i64, u64
+        );
+        range_integer!(
+            /// Generates an `i32` uniformly distributed within the given range.
+            i32_range, i32, u32
+        );
+        range_integer!(
+            /// Generates an `i16` uniformly distributed within the given range.
+            i16_range, i16, u16
+        );
+        range_integer!(
+            /// Generates an `i8` uniformly distributed within the given range.
+            i8_range, i8, u8
+        );
+        range_integer!(
+            /// Generates an `isize` uniformly distributed within the given range.
+            ///
+            /// The value is always derived from 64-bit outputs, so a given range
+            /// yields the same results on 32-bit and 64-bit targets.
+            isize_range, isize, usize
+        );
+
+        /// Generates a `char` uniformly distributed within the given range.
+        ///
+        /// Surrogate code points are not valid `char`s, so a range spanning
+        /// them samples uniformly from the scalar values on either side.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the range is empty.
+        pub fn char(&self, range: impl ::core::ops::RangeBounds<char>) -> char {
+            use ::core::ops::Bound;
+            use $crate::methods::{EMPTY_RANGE, SURROGATE_END, SURROGATE_START};
+
+            const GAP: u32 = SURROGATE_END - SURROGATE_START + 1;
+
+            let low = match range.start_bound() {
+                Bound::Included(&c) => c as u32,
+                Bound::Excluded(&c) if c as u32 == SURROGATE_START - 1 => SURROGATE_END + 1,
+                Bound::Excluded(&c) if c != char::MAX => c as u32 + 1,
+                Bound::Excluded(_) => panic!("{}", EMPTY_RANGE),
+                Bound::Unbounded => 0,
+            };
+            let high = match range.end_bound() {
+                Bound::Included(&c) => c as u32,
+                Bound::Excluded(&c) if c as u32 == SURROGATE_END + 1 => SURROGATE_START - 1,
+                Bound::Excluded(&c) => (c as u32).checked_sub(1).expect(EMPTY_RANGE),
+                Bound::Unbounded => char::MAX as u32,
+            };
+            assert!(low <= high, "{}", EMPTY_RANGE);
+
+            let gap = if low < SURROGATE_START && high > SURROGATE_END {
+                GAP
+            } else {
+                0
+            };
+
+            let mut value = low + self.gen_bounded_u64(u64::from(high - low - gap) + 1) as u32;
+
+            if gap != 0 && value >= SURROGATE_START {
+                value += gap;
+            }
+
+            char::from_u32(value).expect("surrogates are skipped")
+        }
+
+        /// Generates a `char` uniformly distributed over all Unicode scalar
+        /// values.
+        pub fn any_char(&self) -> char {
+            self.char(..)
+        }
+
+        /// Generates an ASCII letter or digit, `[0-9A-Za-z]`.
+        pub fn alphanumeric(&self) -> char {
+            let alphabet = $crate::charset::ALPHANUMERIC.as_bytes();
+
+            alphabet[self.gen_bounded_u64(alphabet.len() as u64) as usize] as char
+        }
+
+        /// Generates an ASCII lowercase letter, `[a-z]`.
+        pub fn lowercase(&self) -> char {
+            self.char('a'..='z')
+        }
+
+        /// Generates an ASCII uppercase letter, `[A-Z]`.
+        pub fn uppercase(&self) -> char {
+            self.char('A'..='Z')
+        }
+
+        /// Generates a digit in the given radix, using lowercase letters for
+        /// digits above 9.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `radix` is not within `[2, 36]`.
+        pub fn digit(&self, radix: u32) -> char {
+            assert!((2..=36).contains(&radix), "radix must be within [2, 36]");
+
+            char::from_digit(self.gen_bounded_u64(u64::from(radix)) as u32, radix)
+                .expect("digit is below the radix")
+        }
+
+        /// Generates a `String` of `len` characters drawn uniformly from
+        /// `charset`. See [`charset`](crate::charset) for common alphabets.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `charset` is empty.
+        #[cfg(feature = "alloc")]
+        pub fn string(&self, charset: &str, len: usize) -> ::alloc::string::String {
+            use $crate::methods::EMPTY_CHARSET;
+
+            assert!(!charset.is_empty(), "{}", EMPTY_CHARSET);
+
+            if charset.is_ascii() {
+                let bytes = charset.as_bytes();
+
+                (0..len)
+                    .map(|_| bytes[self.gen_bounded_u64(bytes.len() as u64) as usize] as char)
+                    .collect()
+            } else {
+                let chars: ::alloc::vec::Vec<char> = charset.chars().collect();
+
+                self.string_from_chars(&chars, len)
+            }
+        }
+
+        /// Generates a `String` of `len` characters drawn uniformly from
+        /// `charset`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `charset` is empty.
+        #[cfg(feature = "alloc")]
+        pub fn string_from_chars(&self, charset: &[char], len: usize) -> ::alloc::string::String {
+            use $crate::methods::EMPTY_CHARSET;
+
+            assert!(!charset.is_empty(), "{}", EMPTY_CHARSET);
+
+            (0..len)
+                .map(|_| charset[self.gen_bounded_u64(charset.len() as u64) as usize])
+                .collect()
+        }
+
+        /// Generates a 21 character identifier in the style of Nano ID, using
+        /// the URL-safe [`BASE64URL`](crate::charset::BASE64URL) alphabet.
+        ///
+        /// This gives about 126 bits of randomness. For a custom alphabet or
+        /// length, use [`string`](Self::string).
+        #[cfg(feature = "alloc")]
+        pub fn nanoid(&self) -> ::alloc::string::String {
+            use $crate::methods::NANOID_LEN;
+
+            self.string($crate::charset::BASE64URL, NANOID_LEN)
+        }
+
+        /// Shuffles `slice` in place with a Fisher–Yates shuffle.
+        ///
+        /// Indices are drawn from 64-bit outputs, so a given seed produces the
+        /// same permutation on 32-bit and 64-bit targets.
+        pub fn shuffle<T>(&self, slice: &mut [T]) {
+            for i in (1..slice.len()).rev() {
+                let j = self.gen_bounded_u64(i as u64 + 1) as usize;
+                slice.swap(i, j);
+            }
+        }
+
+        /// Moves `k` uniformly chosen elements of `slice` to its front in
+        /// random order, and returns that prefix. The order of the remaining
+        /// elements is unspecified.
+        ///
+        /// If `k` exceeds the length of `slice`, the whole slice is shuffled.
+        /// Like [`shuffle`](Self::shuffle), the result for a given seed is the
+        /// same on 32-bit and 64-bit targets.
+        pub fn partial_shuffle<'a, T>(&self, slice: &'a mut [T], k: usize) -> &'a mut [T] {
+            let len = slice.len();
+            let k = k.min(len);
+
+            for i in 0..k {
+                let j = i + self.gen_bounded_u64((len - i) as u64) as usize;
+                slice.swap(i, j);
+            }
+
+            &mut slice[..k]
+        }
+
+        #[inline]
+        fn choose_index(&self, len: usize) -> Option<usize> {
+            (len > 0).then(|| self.gen_bounded_u64(len as u64) as usize)
+        }
+
+        /// Returns a uniformly chosen element of `slice`, or `None` if it is
+        /// empty.
+        pub fn choose<'a, T>(&self, slice: &'a [T]) -> Option<&'a T> {
+            self.choose_index(slice.len()).map(|i| &slice[i])
+        }
+
+        /// Returns a mutable reference to a uniformly chosen element of
+        /// `slice`, or `None` if it is empty.
+        pub fn choose_mut<'a, T>(&self, slice: &'a mut [T]) -> Option<&'a mut T> {
+            self.choose_index(slice.len()).map(move |i| &mut slice[i])
+        }
+
+        /// Returns a uniformly chosen item of `iter`, or `None` if it is empty.
+        ///
+        /// This uses single-pass reservoir sampling, so the iterator is
+        /// consumed without buffering and its length need not be known in
+        /// advance. Every item after the first costs one draw.
+        pub fn choose_iter<I: IntoIterator>(&self, iter: I) -> Option<I::Item> {
+            let mut iter = iter.into_iter();
+            let mut chosen = iter.next()?;
+
+            for (seen, item) in iter.enumerate() {
+                if self.gen_bounded_u64(seen as u64 + 2) == 0 {
+                    chosen = item;
+                }
+            }
+
+            Some(chosen)
+        }
+
+        /// Returns a uniformly chosen entry of `map`, or `None` if it is
+        /// empty. This walks the map, taking `O(n)` time.
+        #[cfg(feature = "alloc")]
+        pub fn choose_btree_map<'a, K, V>(
+            &self,
+            map: &'a ::alloc::collections::BTreeMap<K, V>,
+        ) -> Option<(&'a K, &'a V)> {
+            self.choose_index(map.len()).and_then(|i| map.iter().nth(i))
+        }
+
+        /// Returns a uniformly chosen item of `set`, or `None` if it is empty.
+        /// This walks the set, taking `O(n)` time.
+        #[cfg(feature = "alloc")]
+        pub fn choose_btree_set<'a, T>(
+            &self,
+            set: &'a ::alloc::collections::BTreeSet<T>,
+        ) -> Option<&'a T> {
+            self.choose_index(set.len()).and_then(|i| set.iter().nth(i))
+        }
+
+        /// Returns a uniformly chosen element of `deque`, or `None` if it is
+        /// empty.
+        #[cfg(feature = "alloc")]
+        pub fn choose_vec_deque<'a, T>(
+            &self,
+            deque: &'a ::alloc::collections::VecDeque<T>,
+        ) -> Option<&'a T> {
+            self.choose_index(deque.len()).and_then(|i| deque.get(i))
+        }
+
+        /// Generates an `f64` uniformly distributed in `[0, 1)`.
+        pub fn f64(&self) -> f64 {
+            use $crate::methods::{F64_MANTISSA, F64_SCALE};
+
+            (self.gen_u64() >> (64 - F64_MANTISSA)) as f64 * F64_SCALE
+        }
+
+        /// Generates an `f64` uniformly distributed in `(0, 1]`.
+        pub fn f64_open_closed(&self) -> f64 {
+            use $crate::methods::{F64_MANTISSA, F64_SCALE};
+
+            ((self.gen_u64() >> (64 - F64_MANTISSA)) + 1) as f64 * F64_SCALE
+        }
+
+        /// Generates an `f64` uniformly distributed in `(0, 1)`.
+        pub fn f64_open(&self) -> f64 {
+            use $crate::methods::{F64_MANTISSA, F64_SCALE};
+
+            ((self.gen_u64() >> (65 - F64_MANTISSA)) as f64 + 0.5) * (F64_SCALE * 2.0)
+        }
+
+        /// Generates an `f64` uniformly distributed in `[0, 1]`.
+        pub fn f64_closed(&self) -> f64 {
+            use $crate::methods::{F64_MANTISSA, F64_SCALE};
+
+            self.gen_bounded_u64((1 << F64_MANTISSA) + 1) as f64 * F64_SCALE
+        }
+
+        /// Generates an `f32` uniformly distributed in `[0, 1)`.
+        pub fn f32(&self) -> f32 {
+            use $crate::methods::{F32_MANTISSA, F32_SCALE};
+
+            (self.gen_u64() >> (64 - F32_MANTISSA)) as f32 * F32_SCALE
+        }
+
+        /// Generates an `f32` uniformly distributed in `(0, 1]`.
+        pub fn f32_open_closed(&self) -> f32 {
+            use $crate::methods::{F32_MANTISSA, F32_SCALE};
+
+            ((self.gen_u64() >> (64 - F32_MANTISSA)) + 1) as f32 * F32_SCALE
+        }
+
+        /// Generates an `f32` uniformly distributed in `(0, 1)`.
+        pub fn f32_open(&self) -> f32 {
+            use $crate::methods::{F32_MANTISSA, F32_SCALE};
+
+            ((self.gen_u64() >> (65 - F32_MANTISSA)) as f32 + 0.5) * (F32_SCALE * 2.0)
+        }
+
+        /// Generates an `f32` uniformly distributed in `[0, 1]`.
+        pub fn f32_closed(&self) -> f32 {
+            use $crate::methods::{F32_MANTISSA, F32_SCALE};
+
+            self.gen_bounded_u64((1 << F32_MANTISSA) + 1) as f32 * F32_SCALE
+        }
+    };
+}
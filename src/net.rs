@@ -0,0 +1,210 @@
+//! Random network addresses and ports for test fixtures.
+
+use core::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::Rng;
+
+const EPHEMERAL_PORTS: core::ops::RangeInclusive<u16> = 49152..=65535;
+
+/// The kind of address to generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// Any address at all.
+    Any,
+    /// `10.0.0.0/8`, `172.16.0.0/12` and `192.168.0.0/16` for IPv4, or the
+    /// unique local `fc00::/7` for IPv6.
+    Private,
+    /// `127.0.0.0/8` for IPv4, or `::1` for IPv6.
+    Loopback,
+    /// `169.254.0.0/16` for IPv4, or `fe80::/10` for IPv6.
+    LinkLocal,
+    /// `224.0.0.0/4` for IPv4, or `ff00::/8` for IPv6.
+    Multicast,
+}
+
+/// Generates an IPv4 address within the given scope.
+///
+/// Private addresses are spread uniformly over all three private blocks, so
+/// most land in `10.0.0.0/8`.
+pub fn ipv4(rng: &Rng, scope: Scope) -> Ipv4Addr {
+    const BLOCK_10_SIZE: u32 = 1 << 24;
+    const BLOCK_172_SIZE: u32 = 1 << 20;
+    const BLOCK_192_SIZE: u32 = 1 << 16;
+
+    match scope {
+        Scope::Any => Ipv4Addr::from(rng.u32()),
+        Scope::Private => {
+            let index = rng.u32_range(..BLOCK_10_SIZE + BLOCK_172_SIZE + BLOCK_192_SIZE);
+
+            let (network, offset) = if index < BLOCK_10_SIZE {
+                (Ipv4Addr::new(10, 0, 0, 0), index)
+            } else if index < BLOCK_10_SIZE + BLOCK_172_SIZE {
+                (Ipv4Addr::new(172, 16, 0, 0), index - BLOCK_10_SIZE)
+            } else {
+                (
+                    Ipv4Addr::new(192, 168, 0, 0),
+                    index - BLOCK_10_SIZE - BLOCK_172_SIZE,
+                )
+            };
+
+            Ipv4Addr::from(u32::from(network) | offset)
+        }
+        Scope::Loopback => ipv4_in(rng, Ipv4Addr::new(127, 0, 0, 0), 8),
+        Scope::LinkLocal => ipv4_in(rng, Ipv4Addr::new(169, 254, 0, 0), 16),
+        Scope::Multicast => ipv4_in(rng, Ipv4Addr::new(224, 0, 0, 0), 4),
+    }
+}
+
+/// Generates an IPv4 address within the CIDR block `network/prefix_len`.
+///
+/// # Panics
+///
+/// Panics if `prefix_len` is greater than 32.
+pub fn ipv4_in(rng: &Rng, network: Ipv4Addr, prefix_len: u8) -> Ipv4Addr {
+    assert!(prefix_len <= 32, "IPv4 prefix length must be at most 32");
+
+    let mask = u32::MAX
+        .checked_shl(32 - u32::from(prefix_len))
+        .unwrap_or(0);
+
+    Ipv4Addr::from((u32::from(network) & mask) | (rng.u32() & !mask))
+}
+
+/// Generates an IPv6 address within the given scope.
+pub fn ipv6(rng: &Rng, scope: Scope) -> Ipv6Addr {
+    match scope {
+        Scope::Any => Ipv6Addr::from(rng.u128()),
+        Scope::Private => ipv6_in(rng, Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 0), 7),
+        Scope::Loopback => Ipv6Addr::LOCALHOST,
+        Scope::LinkLocal => ipv6_in(rng, Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0), 10),
+        Scope::Multicast => ipv6_in(rng, Ipv6Addr::new(0xff00, 0, 0, 0, 0, 0, 0, 0), 8),
+    }
+}
+
+/// Generates an IPv6 address within the CIDR block `network/prefix_len`.
+///
+/// # Panics
+///
+/// Panics if `prefix_len` is greater than 128.
+pub fn ipv6_in(rng: &Rng, network: Ipv6Addr, prefix_len: u8) -> Ipv6Addr {
+    assert!(prefix_len <= 128, "IPv6 prefix length must be at most 128");
+
+    let mask = u128::MAX
+        .checked_shl(128 - u32::from(prefix_len))
+        .unwrap_or(0);
+
+    Ipv6Addr::from((u128::from(network) & mask) | (rng.u128() & !mask))
+}
+
+/// Generates a port from the IANA dynamic range, `49152..=65535`.
+pub fn ephemeral_port(rng: &Rng) -> u16 {
+    rng.u16_range(EPHEMERAL_PORTS)
+}
+
+/// Generates an IPv4 socket address within the given scope, on an
+/// ephemeral port.
+pub fn socket_addr_v4(rng: &Rng, scope: Scope) -> SocketAddrV4 {
+    SocketAddrV4::new(ipv4(rng, scope), ephemeral_port(rng))
+}
+
+/// Generates an IPv6 socket address within the given scope, on an
+/// ephemeral port.
+pub fn socket_addr_v6(rng: &Rng, scope: Scope) -> SocketAddrV6 {
+    SocketAddrV6::new(ipv6(rng, scope), ephemeral_port(rng), 0, 0)
+}
+
+/// Generates an IPv4 or IPv6 socket address, with equal probability, within
+/// the given scope and on an ephemeral port.
+pub fn socket_addr(rng: &Rng, scope: Scope) -> SocketAddr {
+    if rng.bool() {
+        SocketAddr::V4(socket_addr_v4(rng, scope))
+    } else {
+        SocketAddr::V6(socket_addr_v6(rng, scope))
+    }
+}
+
+/// Generates a MAC address with the unicast and locally administered bits
+/// set, so it can never clash with a vendor-assigned address.
+pub fn mac(rng: &Rng) -> [u8; 6] {
+    let mut bytes = [0; 6];
+    rng.fill_bytes(&mut bytes);
+    bytes[0] = (bytes[0] & 0xfc) | 0x02;
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipv4_scopes() {
+        let rng = Rng::with_seed(6);
+
+        for _ in 0..1000 {
+            assert!(ipv4(&rng, Scope::Private).is_private());
+            assert!(ipv4(&rng, Scope::Loopback).is_loopback());
+            assert!(ipv4(&rng, Scope::LinkLocal).is_link_local());
+            assert!(ipv4(&rng, Scope::Multicast).is_multicast());
+        }
+    }
+
+    #[test]
+    fn ipv6_scopes() {
+        let rng = Rng::with_seed(6);
+
+        assert_eq!(ipv6(&rng, Scope::Loopback), Ipv6Addr::LOCALHOST);
+
+        for _ in 0..1000 {
+            assert_eq!(ipv6(&rng, Scope::Private).segments()[0] & 0xfe00, 0xfc00);
+            assert_eq!(ipv6(&rng, Scope::LinkLocal).segments()[0] & 0xffc0, 0xfe80);
+            assert!(ipv6(&rng, Scope::Multicast).is_multicast());
+        }
+    }
+
+    #[test]
+    fn cidr_blocks() {
+        let rng = Rng::with_seed(6);
+
+        for _ in 0..1000 {
+            let addr = ipv4_in(&rng, Ipv4Addr::new(192, 0, 2, 77), 24);
+            assert_eq!(addr.octets()[..3], [192, 0, 2]);
+
+            let addr = ipv6_in(&rng, Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32);
+            assert_eq!(addr.segments()[..2], [0x2001, 0xdb8]);
+        }
+
+        let host = Ipv4Addr::new(10, 1, 2, 3);
+        assert_eq!(ipv4_in(&rng, host, 32), host);
+        ipv4_in(&rng, host, 0);
+        ipv6_in(&rng, Ipv6Addr::UNSPECIFIED, 0);
+    }
+
+    #[test]
+    fn ports_and_sockets() {
+        let rng = Rng::with_seed(6);
+
+        for _ in 0..1000 {
+            assert!(EPHEMERAL_PORTS.contains(&ephemeral_port(&rng)));
+
+            let addr = socket_addr(&rng, Scope::Loopback);
+            assert!(addr.ip().is_loopback());
+            assert!(EPHEMERAL_PORTS.contains(&addr.port()));
+        }
+    }
+
+    #[test]
+    fn mac_addresses_are_local_unicast() {
+        let rng = Rng::with_seed(6);
+
+        for _ in 0..1000 {
+            assert_eq!(mac(&rng)[0] & 0x03, 0x02);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 32")]
+    fn oversized_prefix_panics() {
+        ipv4_in(&Rng::with_seed(6), Ipv4Addr::LOCALHOST, 33);
+    }
+}
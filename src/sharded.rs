@@ -0,0 +1,138 @@
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::{Rng, SyncRng};
+
+static NEXT_THREAD_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_INDEX: usize = NEXT_THREAD_INDEX.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A shard padded to its own cache line, so neighbouring shards never share
+/// one. 128 bytes covers the adjacent-line prefetching of modern x86 and the
+/// larger lines of some ARM cores.
+#[derive(Debug)]
+#[repr(align(128))]
+struct Shard(SyncRng);
+
+/// A random number generator for heavily concurrent use, spreading threads
+/// across independent shards to avoid contention.
+///
+/// Each thread is assigned a shard round-robin the first time it draws from
+/// any `ShardedRng`, so threads only contend when there are more of them than
+/// shards. Every shard is derived from one seed, but which thread draws from
+/// which shard depends on scheduling, so only the per-shard streams are
+/// reproducible, not the values observed by a particular thread.
+#[derive(Debug)]
+pub struct ShardedRng {
+    shards: Box<[Shard]>,
+}
+
+impl ShardedRng {
+    /// Creates a generator seeded from the thread-local generator, with one
+    /// shard per available CPU.
+    pub fn new() -> Self {
+        Self::with_seed(Rng::new().state())
+    }
+
+    /// Creates a generator from a 128-bit seed, with one shard per available
+    /// CPU.
+    ///
+    /// Shards are forked from the seed in order, so shard `i` has the same
+    /// stream for a given seed regardless of the shard count.
+    pub fn with_seed(seed: u128) -> Self {
+        let shards = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+        Self::with_seed_and_shards(seed, shards)
+    }
+
+    /// Creates a generator from a 128-bit seed with the given number of
+    /// shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_seed_and_shards(seed: u128, shards: usize) -> Self {
+        assert!(shards > 0, "a sharded generator needs at least one shard");
+
+        let root = Rng::with_seed(seed);
+        let shards = (0..shards)
+            .map(|_| Shard(SyncRng::from_odd_state(root.fork().state())))
+            .collect();
+
+        ShardedRng { shards }
+    }
+
+    /// Returns the number of shards.
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    #[inline]
+    fn gen_u64(&self) -> u64 {
+        let index = THREAD_INDEX.with(|i| *i) % self.shards.len();
+
+        self.shards[index].0.gen_u64()
+    }
+}
+
+impl ShardedRng {
+    rng_methods!();
+}
+
+impl Default for ShardedRng {
+    #[inline]
+    fn default() -> ShardedRng {
+        ShardedRng::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::thread;
+
+    use crate::{Rng, ShardedRng};
+
+    #[test]
+    fn shards_are_seeded_deterministically() {
+        let a = ShardedRng::with_seed_and_shards(4, 1);
+        let b = ShardedRng::with_seed_and_shards(4, 1);
+        let shard = Rng::with_seed(4).fork();
+
+        assert!((0..100).all(|_| {
+            let value = a.u64();
+            value == b.u64() && value == shard.u64()
+        }));
+    }
+
+    #[test]
+    fn concurrent_threads_draw_distinct_values() {
+        const THREADS: usize = 8;
+        const DRAWS: usize = 1000;
+
+        let shared = ShardedRng::with_seed_and_shards(4, 3);
+
+        let drawn: Vec<u64> = thread::scope(|s| {
+            let handles: Vec<_> = (0..THREADS)
+                .map(|_| s.spawn(|| (0..DRAWS).map(|_| shared.u64()).collect::<Vec<_>>()))
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect()
+        });
+
+        let unique: HashSet<u64> = drawn.iter().copied().collect();
+        assert_eq!(unique.len(), THREADS * DRAWS);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one shard")]
+    fn zero_shards_panics() {
+        ShardedRng::with_seed_and_shards(0, 0);
+    }
+}
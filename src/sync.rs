@@ -0,0 +1,282 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::hint;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::{mix, Leapfrog, Rng, EVEN_STATE};
+
+/// A random number generator that can be shared between threads and stored in
+/// a `static`.
+///
+/// ```
+/// use lehmerand::SyncRng;
+///
+/// static GLOBAL: SyncRng = SyncRng::with_seed(42);
+///
+/// let roll = GLOBAL.u8_range(1..=6);
+/// assert!((1..=6).contains(&roll));
+/// ```
+///
+/// The 128-bit state is guarded by a spinlock, and every draw takes the lock
+/// for the duration of a single multiplication. Uncontended draws cost one
+/// atomic exchange more than [`Rng`], but when many threads draw
+/// at once they serialize on the lock and bounce its cache line between
+/// cores. For hot paths, give each thread its own [`Rng`] instead.
+#[derive(Debug)]
+pub struct SyncRng {
+    lock: AtomicBool,
+    high: AtomicU64,
+    low: AtomicU64,
+}
+
+impl SyncRng {
+    /// Creates a generator forked from the thread-local generator.
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self::from_odd_state(Rng::new().state())
+    }
+
+    /// Creates a generator from a 128-bit seed, producing the same stream as
+    /// [`Rng::with_seed`].
+    pub const fn with_seed(seed: u128) -> Self {
+        Self::from_odd_state(mix(seed) | 1)
+    }
+
+    /// Creates a generator from a 64-bit seed, producing the same stream as
+    /// [`Rng::with_seed_u64`].
+    pub const fn with_seed_u64(seed: u64) -> Self {
+        Self::with_seed(seed as u128)
+    }
+
+    /// Creates a generator from a seed of arbitrary length, producing the
+    /// same stream as [`Rng::from_seed_bytes`].
+    pub fn from_seed_bytes(bytes: &[u8]) -> Self {
+        Self::from_odd_state(Rng::from_seed_bytes(bytes).state())
+    }
+
+    /// Creates a generator from a state previously obtained with
+    /// [`SyncRng::state`] or [`Rng::state`].
+    ///
+    /// Returns `None` if `state` is even.
+    pub const fn from_state(state: u128) -> Option<Self> {
+        if state & 1 == 1 {
+            Some(Self::from_odd_state(state))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) const fn from_odd_state(state: u128) -> Self {
+        SyncRng {
+            lock: AtomicBool::new(false),
+            high: AtomicU64::new((state >> 64) as u64),
+            low: AtomicU64::new(state as u64),
+        }
+    }
+
+    /// Returns the current internal state. See [`Rng::state`].
+    pub fn state(&self) -> u128 {
+        self.with_rng(|r| r.state())
+    }
+
+    /// Restores a state previously obtained with [`SyncRng::state`] or
+    /// [`Rng::state`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `state` is even.
+    pub fn restore(&self, state: u128) {
+        assert!(state & 1 == 1, "{}", EVEN_STATE);
+
+        self.with_rng(|r| r.0.set(state));
+    }
+
+    /// Advances this generator and returns an uncorrelated child. See
+    /// [`Rng::fork`].
+    pub fn fork(&self) -> SyncRng {
+        Self::from_odd_state(self.with_rng(|r| r.fork().state()))
+    }
+
+    /// Advances the generator by `n` steps. See [`Rng::advance`].
+    pub fn advance(&self, n: u128) {
+        self.with_rng(|r| r.advance(n));
+    }
+
+    /// Returns a new generator positioned `n` steps ahead of this one. See
+    /// [`Rng::jumped`].
+    pub fn jumped(&self, n: u128) -> SyncRng {
+        Self::from_odd_state(self.snapshot().jumped(n).state())
+    }
+
+    /// Steps the generator back by `n` steps. See [`Rng::rewind`].
+    pub fn rewind(&self, n: u128) {
+        self.with_rng(|r| r.rewind(n));
+    }
+
+    /// Undoes the most recent step, returning the `u64` it produced. See
+    /// [`Rng::prev_u64`].
+    pub fn prev_u64(&self) -> u64 {
+        self.with_rng(|r| r.prev_u64())
+    }
+
+    /// Splits off a generator that owns the next block of 2^64 steps. See
+    /// [`Rng::split`].
+    pub fn split(&self) -> SyncRng {
+        Self::from_odd_state(self.with_rng(|r| r.split().state()))
+    }
+
+    /// Splits off `k` generators over consecutive blocks. See
+    /// [`Rng::split_n`].
+    #[cfg(feature = "alloc")]
+    pub fn split_n(&self, k: usize) -> Vec<SyncRng> {
+        (0..k).map(|_| self.split()).collect()
+    }
+
+    /// Returns a generator producing every `k`-th output of this generator,
+    /// starting at output `i`. See [`Rng::leapfrog`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= k`.
+    pub fn leapfrog(&self, k: usize, i: usize) -> Leapfrog {
+        self.snapshot().leapfrog(k, i)
+    }
+
+    /// Returns how many steps this generator must advance to reach the state
+    /// of `other`. See [`Rng::distance_to`].
+    pub fn distance_to(&self, other: &SyncRng) -> Option<u128> {
+        self.snapshot().distance_to(&other.snapshot())
+    }
+
+    fn snapshot(&self) -> Rng {
+        self.with_rng(|r| r.clone())
+    }
+
+    /// Runs `f` on the state while holding the lock. `f` must not panic, or
+    /// the lock is never released.
+    #[inline]
+    fn with_rng<R>(&self, f: impl FnOnce(&Rng) -> R) -> R {
+        while self
+            .lock
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            while self.lock.load(Ordering::Relaxed) {
+                hint::spin_loop();
+            }
+        }
+
+        let rng = Rng(Cell::new(
+            u128::from(self.high.load(Ordering::Relaxed)) << 64
+                | u128::from(self.low.load(Ordering::Relaxed)),
+        ));
+        let result = f(&rng);
+        let state = rng.state();
+
+        self.high.store((state >> 64) as u64, Ordering::Relaxed);
+        self.low.store(state as u64, Ordering::Relaxed);
+        self.lock.store(false, Ordering::Release);
+
+        result
+    }
+
+    #[inline]
+    pub(crate) fn gen_u64(&self) -> u64 {
+        self.with_rng(|r| r.gen_u64())
+    }
+}
+
+impl SyncRng {
+    rng_methods!();
+}
+
+#[cfg(feature = "std")]
+impl Default for SyncRng {
+    #[inline]
+    fn default() -> SyncRng {
+        SyncRng::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::thread;
+
+    use crate::{Rng, SyncRng};
+
+    #[test]
+    fn matches_rng_stream() {
+        static SHARED: SyncRng = SyncRng::with_seed(8);
+        let rng = Rng::with_seed(8);
+
+        assert!((0..100).all(|_| SHARED.u64() == rng.u64()));
+    }
+
+    #[test]
+    fn state_api_matches_rng() {
+        let shared = SyncRng::with_seed_u64(8);
+        let rng = Rng::with_seed_u64(8);
+
+        assert_eq!(shared.state(), rng.state());
+        assert_eq!(
+            SyncRng::from_seed_bytes(b"seed").state(),
+            Rng::from_seed_bytes(b"seed").state()
+        );
+
+        shared.advance(1000);
+        rng.advance(1000);
+        assert_eq!(shared.state(), rng.state());
+
+        assert_eq!(shared.jumped(50).state(), rng.jumped(50).state());
+        assert_eq!(shared.fork().state(), rng.fork().state());
+        assert_eq!(shared.split().state(), rng.split().state());
+        assert_eq!(shared.leapfrog(3, 1).u64(), rng.leapfrog(3, 1).u64());
+
+        let value = shared.u64();
+        assert_eq!(shared.prev_u64(), value);
+        shared.rewind(10);
+        rng.rewind(10);
+        assert_eq!(shared.state(), rng.state());
+
+        let ahead = shared.jumped(77);
+        assert_eq!(shared.distance_to(&ahead), Some(77));
+
+        let state = shared.state();
+        shared.u64();
+        shared.restore(state);
+        assert_eq!(SyncRng::from_state(state).unwrap().u64(), shared.u64());
+        assert!(SyncRng::from_state(state - 1).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "must be odd")]
+    fn restore_rejects_even_state() {
+        SyncRng::with_seed(0).restore(2);
+    }
+
+    #[test]
+    fn concurrent_draws_are_never_lost() {
+        const THREADS: usize = 4;
+        const DRAWS: usize = 1000;
+
+        let shared = SyncRng::with_seed(8);
+
+        let drawn: HashSet<u64> = thread::scope(|s| {
+            let handles: Vec<_> = (0..THREADS)
+                .map(|_| s.spawn(|| (0..DRAWS).map(|_| shared.u64()).collect::<Vec<_>>()))
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect()
+        });
+
+        let rng = Rng::with_seed(8);
+        let serial: HashSet<u64> = (0..THREADS * DRAWS).map(|_| rng.u64()).collect();
+
+        assert_eq!(drawn, serial);
+    }
+}